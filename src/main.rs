@@ -1,5 +1,7 @@
 use std::{
-    collections::VecDeque,
+    collections::{HashMap, HashSet, VecDeque},
+    fs,
+    path::Path,
     thread,
     time,
     time::Duration,
@@ -10,7 +12,9 @@ use std::{
     }
 };
 use rand::Rng;
+use serde::Deserialize;
 use termion::{
+    color,
     event::Key,
     input::TermRead,
     raw::IntoRawMode,
@@ -21,9 +25,68 @@ use termion::{
 const SNAKE_CHAR: char = '■';
 const BG_CHAR: char = '.';
 const FRUIT_CHAR: char = 'x';
+const WALL_CHAR: char = '#';
+const BONUS_FRUIT_CHAR: char = 'B';
+const BOMB_FRUIT_CHAR: char = '*';
+const SPEED_FRUIT_CHAR: char = '>';
+const SPEED_BOOST_FRAMES: u32 = 40;
 const FRAME_DELAY: Duration = time::Duration::from_millis(80);
+const MIN_BOARD_SIZE: usize = 3;
 
-#[derive(Clone, Copy)]
+// Tunable board size, pacing and glyphs, loaded from a TOML or JSON file
+// passed on the command line so players can adjust difficulty and
+// appearance without recompiling. Falls back to the compiled-in defaults
+// above when no file is given.
+#[derive(Deserialize)]
+struct Config {
+    width: usize,
+    height: usize,
+    frame_delay_ms: u64,
+    snake_char: char,
+    bg_char: char,
+    fruit_char: char,
+    wrap_walls: bool,
+}
+
+impl Config {
+    fn default() -> Config {
+        Config {
+            width: 20,
+            height: 20,
+            frame_delay_ms: FRAME_DELAY.as_millis() as u64,
+            snake_char: SNAKE_CHAR,
+            bg_char: BG_CHAR,
+            fruit_char: FRUIT_CHAR,
+            wrap_walls: true,
+        }
+    }
+
+    // Parse `path` as TOML, or as JSON if its extension is `.json`. Falls
+    // back to `Config::default()` when the file can't be read, or when it
+    // specifies a board too small for the snake to spawn on.
+    fn load(path: &str) -> Config {
+        let contents = match fs::read_to_string(path) {
+            Ok(contents) => contents,
+            Err(_) => return Config::default(),
+        };
+
+        let is_json = Path::new(path).extension().is_some_and(|ext| ext == "json");
+        let config: Config = if is_json {
+            serde_json::from_str(&contents).expect("invalid config JSON")
+        } else {
+            toml::from_str(&contents).expect("invalid config TOML")
+        };
+
+        if config.width < MIN_BOARD_SIZE || config.height < MIN_BOARD_SIZE {
+            eprintln!("config width/height must be at least {MIN_BOARD_SIZE}; using defaults");
+            return Config::default();
+        }
+
+        config
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
 enum Direction {
     UP,
     DOWN,
@@ -31,6 +94,25 @@ enum Direction {
     RIGHT,
 }
 
+#[derive(Clone, Copy, PartialEq)]
+enum GameState {
+    Playing,
+    GameOver,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum FruitKind {
+    Normal,
+    Bonus,
+    Bomb,
+    Speed,
+}
+
+struct Fruit {
+    position: (usize, usize),
+    kind: FruitKind,
+}
+
 struct Snake {
     size: usize,
     direction: Direction,
@@ -54,6 +136,19 @@ impl Snake {
         self.grow += 1;
     }
 
+    // Removes `n` segments from the tail end immediately. Returns false
+    // (leaving the snake untouched) if it doesn't have enough segments to
+    // spare, so the caller can end the game instead.
+    fn shrink(&mut self, n: usize) -> bool {
+        if self.positions.len() <= n {
+            return false;
+        }
+        for _ in 0..n {
+            self.positions.remove(0);
+        }
+        true
+    }
+
     fn move_position(&mut self, board: &Board) -> (usize, usize) {
         let tail_pos: (usize, usize);
         // Get the tail position
@@ -110,38 +205,142 @@ struct Board {
     height: usize,
     width: usize,
     cells: Vec<Vec<char>>,
+    walls: Vec<Vec<bool>>,
+    snake_char: char,
+    bg_char: char,
+    fruit_char: char,
 }
 
 impl Board {
-    fn new(height: usize, width: usize) -> Board {
+    fn new(config: &Config) -> Board {
         let board = Board {
-            height,
-            width,
-            cells: vec![vec![BG_CHAR; width]; height]
+            height: config.height,
+            width: config.width,
+            cells: vec![vec![config.bg_char; config.width]; config.height],
+            walls: vec![vec![false; config.width]; config.height],
+            snake_char: config.snake_char,
+            bg_char: config.bg_char,
+            fruit_char: config.fruit_char,
         };
 
         return board;
     }
 
+    // Seed the playfield with a cave layout: fill interior cells with walls
+    // at random (leaving the snake's spawn clear), then smooth the result
+    // with a few cellular-automata passes so the walls form rooms and
+    // corridors rather than noise.
+    fn generate_caves(&mut self, fill_prob: f64, iterations: usize) {
+        let mut rng = rand::thread_rng();
+        let spawn_x = self.width / 2;
+        let spawn_y = self.height / 2;
+
+        for y in 0..self.height {
+            for x in 0..self.width {
+                let near_spawn = (x as isize - spawn_x as isize).abs() <= 2
+                    && (y as isize - spawn_y as isize).abs() <= 2;
+                self.walls[y][x] = !near_spawn && rng.gen_bool(fill_prob);
+            }
+        }
+
+        for _ in 0..iterations {
+            self.smooth_caves();
+        }
+
+        for y in 0..self.height {
+            for x in 0..self.width {
+                self.cells[y][x] = if self.walls[y][x] { WALL_CHAR } else { self.bg_char };
+            }
+        }
+    }
+
+    // One smoothing pass: a cell becomes a wall if 5 or more of its 8
+    // neighbors are walls (out-of-bounds counts as a wall), open otherwise.
+    fn smooth_caves(&mut self) {
+        let mut next = self.walls.clone();
+        for (y, row) in next.iter_mut().enumerate() {
+            for (x, is_wall) in row.iter_mut().enumerate() {
+                *is_wall = self.wall_neighbor_count(x, y) >= 5;
+            }
+        }
+        self.walls = next;
+    }
+
+    fn wall_neighbor_count(&self, x: usize, y: usize) -> usize {
+        let mut count = 0;
+        for dy in -1isize..=1 {
+            for dx in -1isize..=1 {
+                if dx == 0 && dy == 0 {
+                    continue;
+                }
+                let nx = x as isize + dx;
+                let ny = y as isize + dy;
+                let is_wall = nx < 0
+                    || ny < 0
+                    || nx >= self.width as isize
+                    || ny >= self.height as isize
+                    || self.walls[ny as usize][nx as usize];
+                if is_wall {
+                    count += 1;
+                }
+            }
+        }
+        count
+    }
+
+    fn is_wall(&self, pos: (usize, usize)) -> bool {
+        let (x, y) = pos;
+        self.walls[y][x]
+    }
+
     fn draw_snake(&mut self, snake: &Snake, old_position: (usize, usize)) {
         let (x, y) = old_position;
-        self.cells[y][x] = BG_CHAR;
+        self.cells[y][x] = if self.walls[y][x] { WALL_CHAR } else { self.bg_char };
         for position in snake.positions.iter() {
             let (x, y) = position;
-            self.cells[*y][*x] = SNAKE_CHAR;
+            self.cells[*y][*x] = self.snake_char;
         }
     }
 
-    fn draw_fruit(&mut self, fruit_position: (usize, usize)) {
-        let (x, y) = fruit_position;
-        self.cells[y][x] = FRUIT_CHAR;
+    fn draw_fruit(&mut self, fruit: &Fruit) {
+        let (x, y) = fruit.position;
+        self.cells[y][x] = match fruit.kind {
+            FruitKind::Normal => self.fruit_char,
+            FruitKind::Bonus => BONUS_FRUIT_CHAR,
+            FruitKind::Bomb => BOMB_FRUIT_CHAR,
+            FruitKind::Speed => SPEED_FRUIT_CHAR,
+        };
+    }
+
+    // Up/down/left/right neighbors of `pos`, wrapping around the edges the
+    // same way `Snake::move_position` does, paired with the direction that
+    // reaches each one.
+    fn neighbors(&self, pos: (usize, usize)) -> Vec<((usize, usize), Direction)> {
+        let (x, y) = pos;
+        let up_y = if y == 0 { self.height - 1 } else { y - 1 };
+        let down_y = if y + 1 >= self.height { 0 } else { y + 1 };
+        let left_x = if x == 0 { self.width - 1 } else { x - 1 };
+        let right_x = if x + 1 >= self.width { 0 } else { x + 1 };
+
+        vec![
+            ((x, up_y), Direction::UP),
+            ((x, down_y), Direction::DOWN),
+            ((left_x, y), Direction::LEFT),
+            ((right_x, y), Direction::RIGHT),
+        ]
     }
 }
 
 struct Game {
     board: Board,
     snake: Snake,
-    fruit_position: (usize, usize)
+    fruit: Fruit,
+    frame_delay: Duration,
+    base_frame_delay: Duration,
+    speed_boost_remaining: u32,
+    wrap_walls: bool,
+    state: GameState,
+    score: u32,
 }
 
 impl Game {
@@ -157,25 +356,113 @@ impl Game {
         .unwrap();
         stdout.flush().unwrap();
 
+        if self.state == GameState::GameOver {
+            write!(
+                stdout,
+                "\r\n  Game over — score: {}\r\n\r\n  Press r to restart, q to quit\r\n",
+                self.score
+            )
+            .unwrap();
+            stdout.flush().unwrap();
+            return;
+        }
+
+        let inner_width = self.board.width * 2;
+        let title = format!(" Score: {} ", self.score);
+        let fill = "═".repeat(inner_width.saturating_sub(title.chars().count()));
+        write!(stdout, "\r╔{title}{fill}╗\n").unwrap();
+
         for row in 0..self.board.height {
-            write!(stdout, "\r").unwrap();
-            let line: String = self.board.cells[row].iter().map(|ch| format!("{ch} ", ch=ch)).collect();
-            write!(stdout, "{line}\n").unwrap();
+            write!(stdout, "\r║").unwrap();
+            let line: String = self.board.cells[row].iter().map(|ch| self.colored_glyph(*ch)).collect();
+            write!(stdout, "{line}║\n").unwrap();
         }
 
+        write!(stdout, "\r╚{}╝\n", "═".repeat(inner_width)).unwrap();
         write!(stdout, "\r\nq to exit; Control with arrow keys").unwrap();
 
         stdout.flush().unwrap();
     }
 
+    // Colorizes a board glyph for display: the snake green, walls white,
+    // any fruit kind red, and the background left plain.
+    fn colored_glyph(&self, ch: char) -> String {
+        let colored = match ch {
+            c if c == self.board.snake_char => format!("{}{c}{}", color::Fg(color::Green), color::Fg(color::Reset)),
+            c if c == WALL_CHAR => format!("{}{c}{}", color::Fg(color::White), color::Fg(color::Reset)),
+            c if c == self.board.fruit_char
+                || c == BONUS_FRUIT_CHAR
+                || c == BOMB_FRUIT_CHAR
+                || c == SPEED_FRUIT_CHAR =>
+                format!("{}{c}{}", color::Fg(color::Red), color::Fg(color::Reset)),
+            c => c.to_string(),
+        };
+        format!("{colored} ")
+    }
+
+    fn end_game(&mut self) {
+        self.state = GameState::GameOver;
+    }
+
+    // Whether the snake's next move would hit its own body, an internal
+    // wall, or (when `wrap_walls` is off) the edge of the board.
+    fn detect_collision(&self) -> bool {
+        let head = *self.snake.positions.back().unwrap();
+        let tail = *self.snake.positions.front().unwrap();
+        let (x, y) = head;
 
+        let hits_edge = match self.snake.direction {
+            Direction::UP => y == 0,
+            Direction::DOWN => y + 1 >= self.board.height,
+            Direction::LEFT => x == 0,
+            Direction::RIGHT => x + 1 >= self.board.width,
+        };
+        if hits_edge && !self.wrap_walls {
+            return true;
+        }
+
+        let next = self.board.neighbors(head)
+            .into_iter()
+            .find(|(_, direction)| *direction == self.snake.direction)
+            .unwrap()
+            .0;
+
+        if self.board.is_wall(next) {
+            return true;
+        }
+
+        // The tail only vacates this frame if the snake isn't still growing.
+        let tail_vacates = self.snake.grow == 0;
+        (!tail_vacates || next != tail) && self.snake.positions.contains(&next)
+    }
 
     fn step(&mut self) {
+        if self.state == GameState::GameOver {
+            return;
+        }
+
+        if self.detect_collision() {
+            self.end_game();
+            return;
+        }
+
         /* Move the snakes position and update the board */
         let old_pos = self.snake.move_position(&self.board);
         self.board.draw_snake(&self.snake, old_pos);
         self.check_fruit();
-        self.board.draw_fruit(self.fruit_position);
+        self.board.draw_fruit(&self.fruit);
+        self.tick_speed_boost();
+    }
+
+    // Counts down an active Speed fruit's effect and restores the normal
+    // pace once it expires.
+    fn tick_speed_boost(&mut self) {
+        if self.speed_boost_remaining > 0 {
+            self.speed_boost_remaining -= 1;
+            if self.speed_boost_remaining == 0 {
+                self.frame_delay = self.base_frame_delay;
+            }
+        }
     }
 
     fn keyboard_action(&mut self, key: termion::event::Key) {
@@ -197,33 +484,170 @@ impl Game {
         }
     }
 
-    fn new_fruit_position(&mut self) {
+    // Direction to move this frame when flying on autopilot: a BFS path to
+    // the fruit over the free cells of the board, or a survival move if the
+    // fruit is unreachable.
+    fn autopilot_direction(&self) -> Direction {
+        let head = *self.snake.positions.back().unwrap();
+        let tail = *self.snake.positions.front().unwrap();
+
+        let mut blocked: HashSet<(usize, usize)> = self.snake.positions.iter().copied().collect();
+        // The tail only vacates this frame if the snake isn't still growing.
+        if self.snake.grow == 0 {
+            blocked.remove(&tail);
+        }
+
+        if let Some(path) = self.bfs_path(head, self.fruit.position, &blocked) {
+            if path.len() > 1 {
+                let next = path[1];
+                if let Some((_, direction)) = self.board.neighbors(head).into_iter().find(|(pos, _)| *pos == next) {
+                    return direction;
+                }
+            }
+        }
+
+        self.survival_direction(head, &blocked)
+    }
+
+    // Breadth-first search over the board's wrap-around neighbor graph,
+    // skipping `blocked` cells, returning the cell path from `start` to
+    // `goal` inclusive if one exists.
+    fn bfs_path(&self, start: (usize, usize), goal: (usize, usize), blocked: &HashSet<(usize, usize)>) -> Option<Vec<(usize, usize)>> {
+        let mut queue: VecDeque<(usize, usize)> = VecDeque::new();
+        let mut came_from: HashMap<(usize, usize), (usize, usize)> = HashMap::new();
+        queue.push_back(start);
+        came_from.insert(start, start);
+
+        while let Some(current) = queue.pop_front() {
+            if current == goal {
+                let mut path = vec![current];
+                let mut node = current;
+                while node != start {
+                    node = came_from[&node];
+                    path.push(node);
+                }
+                path.reverse();
+                return Some(path);
+            }
+
+            for (neighbor, _) in self.board.neighbors(current) {
+                if blocked.contains(&neighbor) || self.board.is_wall(neighbor) || came_from.contains_key(&neighbor) {
+                    continue;
+                }
+                came_from.insert(neighbor, current);
+                queue.push_back(neighbor);
+            }
+        }
+
+        None
+    }
+
+    // Fallback when no path to the fruit exists: pick any free neighbor
+    // that still has a free neighbor of its own, so the snake doesn't
+    // immediately trap itself against its body.
+    fn survival_direction(&self, head: (usize, usize), blocked: &HashSet<(usize, usize)>) -> Direction {
+        for (neighbor, direction) in self.board.neighbors(head) {
+            if blocked.contains(&neighbor) || self.board.is_wall(neighbor) {
+                continue;
+            }
+            if self.board.neighbors(neighbor).iter().any(|(pos, _)| !blocked.contains(pos) && !self.board.is_wall(*pos)) {
+                return direction;
+            }
+        }
+
+        self.snake.direction
+    }
+
+    // Picks a random cell that isn't a wall (it may lie under the snake's
+    // body) and a fruit kind weighted toward Normal, with the special kinds
+    // turning up as rarer treats.
+    fn new_fruit(&mut self) {
         let mut rng = rand::thread_rng();
-        let x: usize = rng.gen_range(0..self.board.width);
-        let y: usize = rng.gen_range(0..self.board.height);
-        self.fruit_position = (x, y);
+        let position = loop {
+            let x: usize = rng.gen_range(0..self.board.width);
+            let y: usize = rng.gen_range(0..self.board.height);
+            if !self.board.is_wall((x, y)) {
+                break (x, y);
+            }
+        };
+
+        let kind = match rng.gen_range(0..100) {
+            0..=69 => FruitKind::Normal,
+            70..=84 => FruitKind::Bonus,
+            85..=94 => FruitKind::Bomb,
+            _ => FruitKind::Speed,
+        };
+
+        self.fruit = Fruit { position, kind };
     }
 
     fn check_fruit(&mut self) {
         // Check if fruit in snake positions
-        if self.snake.positions.contains(&self.fruit_position) {
-            self.new_fruit_position();
-            self.snake.grow();
+        if self.snake.positions.contains(&self.fruit.position) {
+            match self.fruit.kind {
+                FruitKind::Normal => {
+                    self.snake.grow();
+                    self.score += 1;
+                },
+                FruitKind::Bonus => {
+                    for _ in 0..3 {
+                        self.snake.grow();
+                    }
+                    self.score += 3;
+                },
+                FruitKind::Bomb => {
+                    if !self.snake.shrink(3) {
+                        self.end_game();
+                        return;
+                    }
+                },
+                FruitKind::Speed => {
+                    self.frame_delay = self.base_frame_delay / 2;
+                    self.speed_boost_remaining = SPEED_BOOST_FRAMES;
+                    self.score += 1;
+                },
+            }
+            self.new_fruit();
         }
     }
 
     fn init(&mut self) {
-        self.new_fruit_position();
-        self.board.draw_fruit(self.fruit_position);
+        self.new_fruit();
+        self.board.draw_fruit(&self.fruit);
         self.board.draw_snake(&self.snake, (0,0));
     }
 }
 
-fn main() {
-    let board = Board::new(20, 20);
+fn new_game(config: &Config) -> Game {
+    let mut board = Board::new(config);
+    board.generate_caves(0.45, 4);
     let snake = Snake::new(&board);
-    let mut game = Game {board, snake, fruit_position: (0,0)};
+    let frame_delay = Duration::from_millis(config.frame_delay_ms);
+    let fruit = Fruit { position: (0, 0), kind: FruitKind::Normal };
+    let mut game = Game {
+        board,
+        snake,
+        fruit,
+        frame_delay,
+        base_frame_delay: frame_delay,
+        speed_boost_remaining: 0,
+        wrap_walls: config.wrap_walls,
+        state: GameState::Playing,
+        score: 0,
+    };
     game.init();
+    game
+}
+
+fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let auto = args.iter().any(|arg| arg == "--auto");
+    let config = match args.iter().find(|arg| !arg.starts_with("--")) {
+        Some(path) => Config::load(path),
+        None => Config::default(),
+    };
+
+    let mut game = new_game(&config);
 
     let stdin: AsyncReader = async_stdin();
     let stdout = stdout().into_raw_mode().unwrap();
@@ -231,17 +655,38 @@ fn main() {
 
     loop {
         let result = keys.next();
-        match result {
-            Some(key) => match key {
-                Ok(k) => {
-                    game.keyboard_action(k)
+
+        if game.state == GameState::GameOver {
+            if let Some(Ok(key)) = result {
+                match key {
+                    Key::Char('r') => game = new_game(&config),
+                    Key::Char('q') => std::process::exit(0x0100),
+                    _ => {},
+                }
+            }
+            game.print(&stdout);
+            thread::sleep(game.frame_delay);
+            continue;
+        }
+
+        if auto {
+            if let Some(Ok(Key::Char('q'))) = result {
+                std::process::exit(0x0100);
+            }
+            game.snake.direction = game.autopilot_direction();
+        } else {
+            match result {
+                Some(key) => match key {
+                    Ok(k) => {
+                        game.keyboard_action(k)
+                    },
+                    _ => {},
                 },
-                _ => {},
-            },
-            _ => (),
+                _ => (),
+            }
         }
         game.step();
         game.print(&stdout);
-        thread::sleep(FRAME_DELAY);
+        thread::sleep(game.frame_delay);
     }
 }